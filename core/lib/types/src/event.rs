@@ -0,0 +1,21 @@
+use crate::{Address, MiniblockNumber, H256};
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+
+/// An event (a.k.a. log) emitted by a contract during VM execution.
+///
+/// `Address`, `MiniblockNumber` and `H256` are plain no_std-clean value types (they derive no
+/// `std`-only traits), so this struct builds under the opt-in `no_std` feature like the rest of
+/// `crate::tx`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VmEvent {
+    /// The miniblock and the index of the transaction within it that produced this event.
+    pub location: (MiniblockNumber, u32),
+    /// The address of the contract that emitted the event.
+    pub address: Address,
+    /// The event's indexed topics (keys), the first of which is conventionally the event
+    /// signature hash.
+    pub indexed_topics: Vec<H256>,
+    /// The non-indexed event data.
+    pub value: Vec<u8>,
+}
@@ -0,0 +1,26 @@
+/// Execution metrics as reported directly by the VM for a single transaction, before batch-level
+/// deduplication against the rest of the block.
+///
+/// [`crate::tx::ExecutionMetrics`] and [`crate::tx::DeduplicatedWritesMetrics`] are both derived
+/// from this via their respective `from_tx_metrics` constructors.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransactionExecutionMetrics {
+    pub published_bytecode_bytes: usize,
+    pub l2_l1_long_messages: usize,
+    pub l2_l1_logs: usize,
+    pub contracts_deployed: u16,
+    pub contracts_used: usize,
+    pub gas_used: usize,
+    pub storage_logs: usize,
+    pub vm_events: usize,
+    pub total_log_queries: usize,
+    pub cycles_used: u32,
+    pub computational_gas_used: u32,
+    pub initial_storage_writes: usize,
+    pub repeated_storage_writes: usize,
+    /// Bytes written to storage slots that were empty before the block, i.e. genuinely new state
+    /// growth rather than an update to existing state.
+    pub new_storage_bytes: usize,
+    /// Bytes written to storage slots that already held a value before the block.
+    pub overwritten_storage_bytes: usize,
+}
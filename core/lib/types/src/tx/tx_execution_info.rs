@@ -1,3 +1,15 @@
+// `ExecutionMetrics`, `DeduplicatedWritesMetrics`, `VmExecutionLogs` and `TxExecutionStatus` are
+// pure data + arithmetic, so this module also builds with the opt-in `no_std` feature (using
+// `alloc` for `Vec`) for use from wasm guests and in-circuit/off-circuit tooling. Everything that
+// isn't — the Prometheus exporter below, and any `std`-only serde paths — is gated out under
+// `no_std` instead, so the public API is otherwise identical either way.
+//
+// `no_std` is opt-in (the crate builds with `std` unless a caller asks for `no_std` explicitly),
+// so this needs `no_std = []` in this crate's Cargo.toml and, since `no_std` can only be applied
+// crate-wide, `#![cfg_attr(feature = "no_std", no_std)]` at the crate root (`lib.rs`).
+#[cfg(feature = "no_std")]
+extern crate alloc;
+
 use crate::commitment::SerializeCommitment;
 use crate::fee::TransactionExecutionMetrics;
 use crate::l2_to_l1_log::L2ToL1Log;
@@ -5,7 +17,78 @@ use crate::writes::{
     InitialStorageWrite, RepeatedStorageWrite, BYTES_PER_DERIVED_KEY, BYTES_PER_ENUMERATION_INDEX,
 };
 use crate::{ProtocolVersionId, StorageLogQuery, VmEvent};
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+#[cfg(not(feature = "no_std"))]
 use std::ops::{Add, AddAssign};
+#[cfg(feature = "no_std")]
+use core::ops::{Add, AddAssign};
+#[cfg(not(feature = "no_std"))]
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Global, Histogram, Metrics};
+
+/// Per-protocol-version caps on the size of events emitted during VM execution.
+///
+/// These mirror the limits the bootloader enforces on event pubdata, so they are resolved here
+/// once per protocol version rather than hardcoded at every call site that inspects `VmEvent`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventSizeLimits {
+    /// Maximum length of an event's data payload, in bytes.
+    pub max_data_length: usize,
+    /// Maximum number of indexed topics (keys) an event may carry.
+    pub max_keys_length: usize,
+    /// Maximum number of events that may be emitted within a single transaction.
+    pub max_n_emitted_events: usize,
+}
+
+impl EventSizeLimits {
+    /// Returns the limits in effect for the given protocol version.
+    pub fn for_version(protocol_version: ProtocolVersionId) -> Self {
+        match protocol_version {
+            version if version >= ProtocolVersionId::Version17 => Self {
+                max_data_length: 8_192,
+                max_keys_length: 4,
+                max_n_emitted_events: 16_384,
+            },
+            _ => Self {
+                max_data_length: 4_096,
+                max_keys_length: 4,
+                max_n_emitted_events: 4_096,
+            },
+        }
+    }
+}
+
+/// Reasons a transaction's [`VmExecutionLogs`] can be rejected for carrying oversized events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventValidationError {
+    DataTooLong { actual: usize, max: usize },
+    TooManyIndexedKeys { actual: usize, max: usize },
+    TooManyEvents { actual: usize, max: usize },
+}
+
+// Implemented by hand (rather than via `thiserror`, which requires `std`) so this error stays
+// usable from the `no_std` build.
+impl core::fmt::Display for EventValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DataTooLong { actual, max } => {
+                write!(f, "event data is too long: {actual} bytes, max is {max}")
+            }
+            Self::TooManyIndexedKeys { actual, max } => {
+                write!(
+                    f,
+                    "event has too many indexed keys: {actual}, max is {max}"
+                )
+            }
+            Self::TooManyEvents { actual, max } => {
+                write!(f, "too many events emitted: {actual}, max is {max}")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+impl std::error::Error for EventValidationError {}
 
 /// Events/storage logs/l2->l1 logs created within transaction execution.
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -17,6 +100,42 @@ pub struct VmExecutionLogs {
     pub total_log_queries_count: usize,
 }
 
+impl VmExecutionLogs {
+    /// Validates that the emitted events don't exceed the per-version size limits, so that
+    /// oversized-event transactions are rejected deterministically instead of silently blowing
+    /// up pubdata.
+    pub fn validate_events(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> Result<(), EventValidationError> {
+        let limits = EventSizeLimits::for_version(protocol_version);
+
+        if self.events.len() > limits.max_n_emitted_events {
+            return Err(EventValidationError::TooManyEvents {
+                actual: self.events.len(),
+                max: limits.max_n_emitted_events,
+            });
+        }
+
+        for event in &self.events {
+            if event.value.len() > limits.max_data_length {
+                return Err(EventValidationError::DataTooLong {
+                    actual: event.value.len(),
+                    max: limits.max_data_length,
+                });
+            }
+            if event.indexed_topics.len() > limits.max_keys_length {
+                return Err(EventValidationError::TooManyIndexedKeys {
+                    actual: event.indexed_topics.len(),
+                    max: limits.max_keys_length,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TxExecutionStatus {
     Success,
@@ -33,6 +152,72 @@ impl TxExecutionStatus {
     }
 }
 
+/// Versioned coefficients driving the [`ExecutionMetrics::size`] and
+/// [`DeduplicatedWritesMetrics::size`] computations.
+///
+/// These used to be compiled-in constants matched on per call site, which meant rolling out a
+/// new protocol version's pricing required a recompile and a new `match` arm wherever a
+/// coefficient was used. Centralizing them here means a new version's pricing is a matter of
+/// adding an entry to this table, and keeps the fee parameters auditable in one place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceGasCosts {
+    /// Milligas charged per byte of L2->L1 long message data.
+    pub milligas_per_data_byte: u64,
+    /// Milligas charged per byte of published contract bytecode.
+    pub milligas_per_code_byte: u64,
+    /// Bytes charged per emitted event, approximating the pubdata overhead of its indexed keys.
+    /// Unlike the `milligas_per_*` fields, this is a direct byte coefficient, not milligas.
+    pub event_key_factor: u64,
+    /// Bytes charged for the derived key of an initial storage write.
+    pub bytes_per_derived_key: u64,
+    /// Bytes charged for the enumeration index of a repeated storage write.
+    pub bytes_per_enumeration_index: u64,
+    /// Bytes charged per byte of value written to a storage slot that was empty before the
+    /// block.
+    pub bytes_per_new_storage_byte: u64,
+    /// Bytes charged per byte of value written to a storage slot that already held a value.
+    pub bytes_per_overwritten_storage_byte: u64,
+}
+
+impl ResourceGasCosts {
+    /// Returns the cost table in effect for the given protocol version.
+    ///
+    /// This mirrors the versioned JSON table these coefficients are meant to be loaded from.
+    pub fn for_version(protocol_version: ProtocolVersionId) -> Self {
+        match protocol_version {
+            version if version >= ProtocolVersionId::Version17 => Self {
+                milligas_per_data_byte: 1_000,
+                milligas_per_code_byte: 1_000,
+                // The legacy `size()` never charged for `vm_events`; this table is a refactor of
+                // the existing coefficients into one place, not a venue for introducing a new
+                // pubdata charge, so this stays `0` until a protocol upgrade explicitly prices
+                // event pubdata and bumps it with justification of its own.
+                event_key_factor: 0,
+                bytes_per_derived_key: BYTES_PER_DERIVED_KEY as u64,
+                bytes_per_enumeration_index: BYTES_PER_ENUMERATION_INDEX as u64,
+                // A newly-written slot has no prior value on L1 to diff against, so its full value
+                // must be published; an overwrite can instead rely on cheaper delta/compaction
+                // against the value already published. This is independent of the derived-key/
+                // enumeration-index overhead, which is charged separately via
+                // `bytes_per_derived_key` above.
+                bytes_per_new_storage_byte: 2,
+                bytes_per_overwritten_storage_byte: 1,
+            },
+            _ => Self {
+                milligas_per_data_byte: 1_000,
+                milligas_per_code_byte: 1_000,
+                event_key_factor: 0,
+                bytes_per_derived_key: BYTES_PER_DERIVED_KEY as u64,
+                bytes_per_enumeration_index: BYTES_PER_ENUMERATION_INDEX as u64,
+                // Unused by the legacy (pre-Version17) `size()` formula below, which instead
+                // charges the full `InitialStorageWrite`/`RepeatedStorageWrite` serialized size.
+                bytes_per_new_storage_byte: 1,
+                bytes_per_overwritten_storage_byte: 1,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct DeduplicatedWritesMetrics {
     /// The number of initial storage writes.
@@ -40,7 +225,14 @@ pub struct DeduplicatedWritesMetrics {
     /// The number of repeated storage writes.
     pub repeated_storage_writes: usize,
     /// This is the total number of bytes used for value updates as part of storage writes.
+    // This field is superseded by `new_storage_bytes` / `overwritten_storage_bytes`, but we need
+    // to keep it for backward compatibility. `from_tx_metrics` sets it to their sum.
     pub total_updated_values_size: usize,
+    /// Bytes written to storage slots that were empty before the block, i.e. genuinely new
+    /// state growth rather than an update to existing state.
+    pub new_storage_bytes: usize,
+    /// Bytes written to storage slots that already held a value before the block.
+    pub overwritten_storage_bytes: usize,
 }
 
 impl DeduplicatedWritesMetrics {
@@ -48,16 +240,24 @@ impl DeduplicatedWritesMetrics {
         Self {
             initial_storage_writes: tx_metrics.initial_storage_writes,
             repeated_storage_writes: tx_metrics.repeated_storage_writes,
-            total_updated_values_size: tx_metrics.total_updated_values_size,
+            // Derived rather than read off `tx_metrics` directly, so this can never drift from
+            // the new/overwritten split below.
+            total_updated_values_size: tx_metrics.new_storage_bytes
+                + tx_metrics.overwritten_storage_bytes,
+            new_storage_bytes: tx_metrics.new_storage_bytes,
+            overwritten_storage_bytes: tx_metrics.overwritten_storage_bytes,
         }
     }
 
     pub fn size(&self, protocol_version: ProtocolVersionId) -> usize {
+        let costs = ResourceGasCosts::for_version(protocol_version);
         match protocol_version {
             version if version >= ProtocolVersionId::Version17 => {
-                self.total_updated_values_size
-                    + (BYTES_PER_DERIVED_KEY as usize) * self.initial_storage_writes
-                    + (BYTES_PER_ENUMERATION_INDEX as usize) * self.repeated_storage_writes
+                self.new_storage_bytes * costs.bytes_per_new_storage_byte as usize
+                    + self.overwritten_storage_bytes * costs.bytes_per_overwritten_storage_byte
+                        as usize
+                    + costs.bytes_per_derived_key as usize * self.initial_storage_writes
+                    + costs.bytes_per_enumeration_index as usize * self.repeated_storage_writes
             }
             _ => {
                 self.initial_storage_writes * InitialStorageWrite::SERIALIZED_SIZE
@@ -67,7 +267,8 @@ impl DeduplicatedWritesMetrics {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(not(feature = "no_std"), derive(serde::Serialize))]
 pub struct ExecutionMetrics {
     pub gas_used: usize,
     pub published_bytecode_bytes: usize,
@@ -99,10 +300,37 @@ impl ExecutionMetrics {
         }
     }
 
-    pub fn size(&self) -> usize {
+    /// Checks whether this batch of metrics still fits within `limit`, treating the computation
+    /// dimension (`cycles_used`/`computational_gas_used`) and the pubdata dimension (the byte
+    /// quantities returned by [`Self::size`]) as independent budgets. A batch builder can seal a
+    /// batch as soon as either budget would be exceeded by the next transaction.
+    pub fn fits_within(
+        &self,
+        protocol_version: ProtocolVersionId,
+        limit: ExecutionMetrics,
+    ) -> bool {
+        let computation_fits = self.cycles_used <= limit.cycles_used
+            && self.computational_gas_used <= limit.computational_gas_used;
+        let pubdata_fits = self.size(protocol_version) <= limit.size(protocol_version);
+
+        computation_fits && pubdata_fits
+    }
+
+    pub fn size(&self, protocol_version: ProtocolVersionId) -> usize {
+        let costs = ResourceGasCosts::for_version(protocol_version);
+
+        // Each milligas-denominated term is divided down to bytes on its own, rather than summed
+        // with the others first: with `milligas_per_data_byte`/`milligas_per_code_byte` both
+        // exact multiples of 1000 this reproduces the pre-versioning byte counts exactly, and it
+        // keeps `event_key_factor` (a direct bytes-per-event coefficient, not milligas) from
+        // being swallowed by a shared floor division.
+        let data_bytes = self.l2_l1_long_messages as u64 * costs.milligas_per_data_byte / 1_000;
+        let code_bytes =
+            self.published_bytecode_bytes as u64 * costs.milligas_per_code_byte / 1_000;
+        let event_bytes = self.vm_events as u64 * costs.event_key_factor;
+
         self.l2_l1_logs * L2ToL1Log::SERIALIZED_SIZE
-            + self.l2_l1_long_messages
-            + self.published_bytecode_bytes
+            + (data_bytes + code_bytes + event_bytes) as usize
     }
 }
 
@@ -110,19 +338,26 @@ impl Add for ExecutionMetrics {
     type Output = ExecutionMetrics;
 
     fn add(self, other: ExecutionMetrics) -> ExecutionMetrics {
+        // Every field is combined with `saturating_add` rather than `+` so that aggregating the
+        // (potentially thousands of) transactions in a batch can never panic or silently wrap.
         ExecutionMetrics {
-            published_bytecode_bytes: self.published_bytecode_bytes
-                + other.published_bytecode_bytes,
-            contracts_deployed: self.contracts_deployed + other.contracts_deployed,
-            contracts_used: self.contracts_used + other.contracts_used,
-            l2_l1_long_messages: self.l2_l1_long_messages + other.l2_l1_long_messages,
-            l2_l1_logs: self.l2_l1_logs + other.l2_l1_logs,
-            gas_used: self.gas_used + other.gas_used,
-            vm_events: self.vm_events + other.vm_events,
-            storage_logs: self.storage_logs + other.storage_logs,
-            total_log_queries: self.total_log_queries + other.total_log_queries,
-            cycles_used: self.cycles_used + other.cycles_used,
-            computational_gas_used: self.computational_gas_used + other.computational_gas_used,
+            published_bytecode_bytes: self
+                .published_bytecode_bytes
+                .saturating_add(other.published_bytecode_bytes),
+            contracts_deployed: self.contracts_deployed.saturating_add(other.contracts_deployed),
+            contracts_used: self.contracts_used.saturating_add(other.contracts_used),
+            l2_l1_long_messages: self
+                .l2_l1_long_messages
+                .saturating_add(other.l2_l1_long_messages),
+            l2_l1_logs: self.l2_l1_logs.saturating_add(other.l2_l1_logs),
+            gas_used: self.gas_used.saturating_add(other.gas_used),
+            vm_events: self.vm_events.saturating_add(other.vm_events),
+            storage_logs: self.storage_logs.saturating_add(other.storage_logs),
+            total_log_queries: self.total_log_queries.saturating_add(other.total_log_queries),
+            cycles_used: self.cycles_used.saturating_add(other.cycles_used),
+            computational_gas_used: self
+                .computational_gas_used
+                .saturating_add(other.computational_gas_used),
         }
     }
 }
@@ -132,3 +367,262 @@ impl AddAssign for ExecutionMetrics {
         *self = *self + other;
     }
 }
+
+#[cfg(not(feature = "no_std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum TxStatusLabel {
+    Success,
+    Failure,
+}
+
+#[cfg(not(feature = "no_std"))]
+impl From<TxExecutionStatus> for TxStatusLabel {
+    fn from(status: TxExecutionStatus) -> Self {
+        match status {
+            TxExecutionStatus::Success => Self::Success,
+            TxExecutionStatus::Failure => Self::Failure,
+        }
+    }
+}
+
+#[cfg(not(feature = "no_std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct TxMetricsLabels {
+    status: TxStatusLabel,
+}
+
+/// Prometheus metrics for per-transaction [`ExecutionMetrics`] / [`DeduplicatedWritesMetrics`],
+/// labeled by [`TxExecutionStatus`] so operators can watch resource consumption and write
+/// amplification live without scraping logs.
+///
+/// Only available when the `no_std` feature is off: it pulls in the `vise` Prometheus registry,
+/// which isn't meaningful (or buildable) in the `no_std` wasm/in-circuit configuration.
+#[cfg(not(feature = "no_std"))]
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "tx_execution")]
+pub struct MetricsRegistry {
+    /// Total gas used across observed transactions.
+    gas_used: Family<TxMetricsLabels, Counter>,
+    /// Total storage logs recorded.
+    storage_logs: Family<TxMetricsLabels, Counter>,
+    /// Total VM events recorded.
+    vm_events: Family<TxMetricsLabels, Counter>,
+    /// Total L2->L1 logs recorded.
+    l2_l1_logs: Family<TxMetricsLabels, Counter>,
+    /// Total published bytecode bytes recorded.
+    published_bytecode_bytes: Family<TxMetricsLabels, Counter>,
+    /// Total contracts deployed.
+    contracts_deployed: Family<TxMetricsLabels, Counter>,
+    /// Per-transaction pubdata `size()`, in bytes.
+    #[metrics(buckets = Buckets::exponential(1.0..=1_048_576.0, 2.0))]
+    tx_size_bytes: Family<TxMetricsLabels, Histogram<f64>>,
+    /// Per-transaction write amplification, i.e. deduplicated-writes `size()`, in bytes.
+    #[metrics(buckets = Buckets::exponential(1.0..=1_048_576.0, 2.0))]
+    writes_size_bytes: Family<TxMetricsLabels, Histogram<f64>>,
+    /// Per-transaction computational gas used.
+    #[metrics(buckets = Buckets::exponential(1.0..=1_000_000.0, 2.0))]
+    computational_gas_used: Family<TxMetricsLabels, Histogram<f64>>,
+}
+
+#[cfg(not(feature = "no_std"))]
+#[vise::register]
+pub static METRICS: Global<MetricsRegistry> = Global::new();
+
+#[cfg(not(feature = "no_std"))]
+impl MetricsRegistry {
+    /// Records `metrics`/`writes` for a transaction that finished with `status`, incrementing the
+    /// counters and histograms labeled by success/failure. Only per-transaction series are
+    /// recorded; the registry has no batch-level gauges for aggregated [`ExecutionMetrics`].
+    pub fn observe(
+        &self,
+        status: TxExecutionStatus,
+        protocol_version: ProtocolVersionId,
+        metrics: &ExecutionMetrics,
+        writes: &DeduplicatedWritesMetrics,
+    ) {
+        let labels = TxMetricsLabels {
+            status: status.into(),
+        };
+
+        self.gas_used[&labels].inc_by(metrics.gas_used as u64);
+        self.storage_logs[&labels].inc_by(metrics.storage_logs as u64);
+        self.vm_events[&labels].inc_by(metrics.vm_events as u64);
+        self.l2_l1_logs[&labels].inc_by(metrics.l2_l1_logs as u64);
+        self.published_bytecode_bytes[&labels].inc_by(metrics.published_bytecode_bytes as u64);
+        self.contracts_deployed[&labels].inc_by(metrics.contracts_deployed.into());
+        self.tx_size_bytes[&labels].observe(metrics.size(protocol_version) as f64);
+        self.writes_size_bytes[&labels].observe(writes.size(protocol_version) as f64);
+        self.computational_gas_used[&labels].observe(metrics.computational_gas_used.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::H256;
+
+    fn event_with(data_len: usize, n_keys: usize) -> VmEvent {
+        VmEvent {
+            value: vec![0u8; data_len],
+            indexed_topics: vec![H256::zero(); n_keys],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_events_accepts_exactly_the_limits() {
+        let limits = EventSizeLimits::for_version(ProtocolVersionId::Version17);
+        let logs = VmExecutionLogs {
+            events: vec![event_with(limits.max_data_length, limits.max_keys_length)],
+            ..Default::default()
+        };
+        assert_eq!(logs.validate_events(ProtocolVersionId::Version17), Ok(()));
+    }
+
+    #[test]
+    fn validate_events_rejects_oversized_data() {
+        let limits = EventSizeLimits::for_version(ProtocolVersionId::Version17);
+        let logs = VmExecutionLogs {
+            events: vec![event_with(limits.max_data_length + 1, 0)],
+            ..Default::default()
+        };
+        assert_eq!(
+            logs.validate_events(ProtocolVersionId::Version17),
+            Err(EventValidationError::DataTooLong {
+                actual: limits.max_data_length + 1,
+                max: limits.max_data_length,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_events_rejects_too_many_indexed_keys() {
+        let limits = EventSizeLimits::for_version(ProtocolVersionId::Version17);
+        let logs = VmExecutionLogs {
+            events: vec![event_with(0, limits.max_keys_length + 1)],
+            ..Default::default()
+        };
+        assert_eq!(
+            logs.validate_events(ProtocolVersionId::Version17),
+            Err(EventValidationError::TooManyIndexedKeys {
+                actual: limits.max_keys_length + 1,
+                max: limits.max_keys_length,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_events_rejects_too_many_events() {
+        let limits = EventSizeLimits::for_version(ProtocolVersionId::Version17);
+        let logs = VmExecutionLogs {
+            events: vec![event_with(0, 0); limits.max_n_emitted_events + 1],
+            ..Default::default()
+        };
+        assert_eq!(
+            logs.validate_events(ProtocolVersionId::Version17),
+            Err(EventValidationError::TooManyEvents {
+                actual: limits.max_n_emitted_events + 1,
+                max: limits.max_n_emitted_events,
+            })
+        );
+    }
+
+    #[test]
+    fn execution_metrics_add_saturates_instead_of_overflowing() {
+        let mut total = ExecutionMetrics {
+            cycles_used: u32::MAX,
+            gas_used: usize::MAX,
+            ..Default::default()
+        };
+        total += ExecutionMetrics {
+            cycles_used: 1,
+            gas_used: 1,
+            ..Default::default()
+        };
+        assert_eq!(total.cycles_used, u32::MAX);
+        assert_eq!(total.gas_used, usize::MAX);
+    }
+
+    #[test]
+    fn execution_metrics_size_does_not_charge_for_events() {
+        // The legacy formula never priced `vm_events`; the versioned cost table must not smuggle
+        // in a new pubdata charge for them under cover of a refactor.
+        let metrics = ExecutionMetrics {
+            vm_events: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(metrics.size(ProtocolVersionId::Version17), 0);
+        assert_eq!(metrics.size(ProtocolVersionId::Version16), 0);
+    }
+
+    #[test]
+    fn execution_metrics_size_matches_legacy_formula_pre_version_17() {
+        let metrics = ExecutionMetrics {
+            l2_l1_long_messages: 10,
+            published_bytecode_bytes: 20,
+            l2_l1_logs: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            metrics.size(ProtocolVersionId::Version16),
+            2 * L2ToL1Log::SERIALIZED_SIZE + 10 + 20
+        );
+    }
+
+    #[test]
+    fn execution_metrics_fits_within_checks_both_dimensions_independently() {
+        let limit = ExecutionMetrics {
+            cycles_used: 100,
+            computational_gas_used: 100,
+            l2_l1_logs: 100,
+            ..Default::default()
+        };
+        let over_computation = ExecutionMetrics {
+            cycles_used: 101,
+            ..Default::default()
+        };
+        let over_pubdata = ExecutionMetrics {
+            l2_l1_logs: 101,
+            ..Default::default()
+        };
+
+        assert!(!over_computation.fits_within(ProtocolVersionId::Version17, limit));
+        assert!(!over_pubdata.fits_within(ProtocolVersionId::Version17, limit));
+        assert!(ExecutionMetrics::default().fits_within(ProtocolVersionId::Version17, limit));
+    }
+
+    #[test]
+    fn deduplicated_writes_size_prices_new_bytes_above_overwritten_bytes_from_version_17() {
+        let writes = DeduplicatedWritesMetrics {
+            new_storage_bytes: 10,
+            overwritten_storage_bytes: 10,
+            ..Default::default()
+        };
+        let costs = ResourceGasCosts::for_version(ProtocolVersionId::Version17);
+
+        assert_eq!(
+            writes.size(ProtocolVersionId::Version17),
+            10 * costs.bytes_per_new_storage_byte as usize
+                + 10 * costs.bytes_per_overwritten_storage_byte as usize
+        );
+        assert!(costs.bytes_per_new_storage_byte > costs.bytes_per_overwritten_storage_byte);
+    }
+
+    #[test]
+    fn deduplicated_writes_from_tx_metrics_reconstructs_total_updated_values_size() {
+        let tx_metrics = TransactionExecutionMetrics {
+            new_storage_bytes: 7,
+            overwritten_storage_bytes: 3,
+            ..Default::default()
+        };
+
+        let writes = DeduplicatedWritesMetrics::from_tx_metrics(&tx_metrics);
+
+        assert_eq!(
+            writes.total_updated_values_size,
+            writes.new_storage_bytes + writes.overwritten_storage_bytes
+        );
+    }
+}